@@ -1,7 +1,48 @@
 use std::alloc::Layout;
-use std::{ptr, slice};
+use std::{mem, ptr, slice};
+use bumpalo::collections::Vec as BumpVec;
 use bumpalo::Bump;
 
+/// Drops the prefix of a slice that has already been initialized, so that a
+/// fallible fill which bails out early doesn't leak whatever the partially
+/// written elements own (heap allocations, file descriptors, etc).
+///
+/// The caller is responsible for bumping `initialized` after each successful
+/// `ptr::write`, and for `mem::forget`-ing the guard once the whole slice is
+/// initialized.
+struct FillGuard<T> {
+    dst: *mut T,
+    initialized: usize,
+}
+
+impl<T> Drop for FillGuard<T> {
+    fn drop(&mut self) {
+        unsafe {
+            ptr::drop_in_place(ptr::slice_from_raw_parts_mut(self.dst, self.initialized));
+        }
+    }
+}
+
+/// Converts a bump-allocated `String` into its backing `&mut str`. Sound
+/// because `bumpalo::collections::String` maintains the UTF-8 invariant for
+/// us the same way `std::String` does.
+fn bump_string_into_mut_str<'bump>(s: bumpalo::collections::String<'bump>) -> &'bump mut str {
+    let bytes = s.into_bytes().into_bump_slice_mut();
+    unsafe { std::str::from_utf8_unchecked_mut(bytes) }
+}
+
+/// The error returned by the `Result`-based `try_alloc_*` fill methods,
+/// distinguishing an allocation failure (the arena couldn't reserve space)
+/// from a failure reported by the caller's closure or iterator. The `Option`
+/// variants return `bumpalo::AllocErr` directly instead.
+#[derive(Debug)]
+pub enum FillError<E> {
+    /// Reserving space for the slice in the arena failed.
+    Alloc(bumpalo::AllocErr),
+    /// The supplied closure or iterator returned an error.
+    Closure(E),
+}
+
 pub trait BumpaloExtend {
     /// Allocates a new slice of size `len` into this `Bump` and returns an
     /// exclusive reference to the copy, early exiting if the function returns Err.
@@ -9,6 +50,10 @@ pub trait BumpaloExtend {
     /// The elements of the slice are initialized using the supplied closure.
     /// The closure argument is the position in the slice.
     ///
+    /// If the closure returns `Err`, the elements written so far are dropped
+    /// in place before the error is returned, so early exit doesn't leak
+    /// whatever they own.
+    ///
     /// ## Panics
     ///
     /// Panics if reserving space for the slice fails.
@@ -131,6 +176,216 @@ pub trait BumpaloExtend {
         I::IntoIter: ExactSizeIterator {
         self.alloc_slice_fill_iter_result(iter.into_iter().map(|v| v.ok_or(()))).ok()
     }
+
+    /// Allocates a slice into this `Bump` and returns an exclusive reference
+    /// to the copy, early exiting if the iterator returns Err.
+    ///
+    /// Unlike [`BumpaloExtend::alloc_slice_fill_iter_result`], the iterator
+    /// doesn't need to be an `ExactSizeIterator`: elements are pushed one at a
+    /// time into a growing bump-allocated `Vec`, so this also works with
+    /// filters, `flat_map`, readers, and other iterators whose length isn't
+    /// known ahead of time.
+    ///
+    /// ## Examples
+    ///
+    /// ```
+    /// use bumpalo_try::BumpaloExtend;
+    /// let bump = bumpalo::Bump::new();
+    /// let x: Result<&mut [i32], ()> = bump.alloc_slice_fill_iter_result_unsized((0..10).filter(|i| i % 2 == 0).map(|i| Ok(i * i)));
+    /// assert_eq!(x.unwrap(), [0, 4, 16, 36, 64]);
+    /// ```
+    ///
+    /// ```
+    /// use bumpalo_try::BumpaloExtend;
+    /// let bump = bumpalo::Bump::new();
+    /// let x: Result<&mut [i32], ()> = bump.alloc_slice_fill_iter_result_unsized([Ok(2), Err(()), Ok(5)]);
+    /// assert_eq!(x, Err(()));
+    /// ```
+    fn alloc_slice_fill_iter_result_unsized<T, E, I>(&self, iter: I) -> Result<&mut [T], E>
+    where
+        I: IntoIterator<Item = Result<T, E>>;
+
+    /// Allocates a slice into this `Bump` and returns an exclusive reference
+    /// to the copy, early exiting if the iterator returns None.
+    ///
+    /// Unlike [`BumpaloExtend::alloc_slice_fill_iter_option`], the iterator
+    /// doesn't need to be an `ExactSizeIterator`.
+    ///
+    /// ## Examples
+    ///
+    /// ```
+    /// use bumpalo_try::BumpaloExtend;
+    /// let bump = bumpalo::Bump::new();
+    /// let x: Option<&mut [i32]> = bump.alloc_slice_fill_iter_option_unsized((0..10i32).filter(|i| i % 2 == 0).map(|i| i.checked_pow(2)));
+    /// assert_eq!(x.unwrap(), [0, 4, 16, 36, 64]);
+    /// ```
+    ///
+    /// ```
+    /// use bumpalo_try::BumpaloExtend;
+    /// let bump = bumpalo::Bump::new();
+    /// let x: Option<&mut [i32]> = bump.alloc_slice_fill_iter_option_unsized([2, 3, i32::MAX].iter().cloned().map(|i| i.checked_pow(2)));
+    /// assert_eq!(x, None);
+    /// ```
+    fn alloc_slice_fill_iter_option_unsized<T, I>(&self, iter: I) -> Option<&mut [T]>
+    where
+        I: IntoIterator<Item = Option<T>> {
+        self.alloc_slice_fill_iter_result_unsized(iter.into_iter().map(|v| v.ok_or(()))).ok()
+    }
+
+    /// Allocates a new slice of size `len` into this `Bump` and returns an
+    /// exclusive reference to the copy, early exiting if reserving the space
+    /// fails or if the function returns Err.
+    ///
+    /// Unlike [`BumpaloExtend::alloc_slice_fill_with_result`], this never
+    /// panics on allocation failure: it reports it as `FillError::Alloc`
+    /// instead, for callers that must not abort on OOM.
+    ///
+    /// ## Examples
+    ///
+    /// ```
+    /// use bumpalo_try::{BumpaloExtend, FillError};
+    /// let bump = bumpalo::Bump::new();
+    /// let x = bump.try_alloc_slice_fill_with_result(5, |i| Ok::<usize, ()>(5 * (i + 1)));
+    /// assert_eq!(x.unwrap(), &[5, 10, 15, 20, 25]);
+    /// ```
+    ///
+    /// ```
+    /// use bumpalo_try::{BumpaloExtend, FillError};
+    /// let bump = bumpalo::Bump::new();
+    /// let x = bump.try_alloc_slice_fill_with_result(5, |i| Err::<usize, ()>(()));
+    /// assert!(matches!(x, Err(FillError::Closure(()))));
+    /// ```
+    fn try_alloc_slice_fill_with_result<T, E, F>(&self, len: usize, f: F) -> Result<&mut [T], FillError<E>>
+    where
+        F: FnMut(usize) -> Result<T, E>;
+
+    /// The `Option` twin of [`BumpaloExtend::try_alloc_slice_fill_with_result`].
+    ///
+    /// The outer `Result` reports allocation failure; the inner `Option`
+    /// reports early exit from the closure, mirroring
+    /// [`BumpaloExtend::alloc_slice_fill_with_option`].
+    ///
+    /// ## Examples
+    ///
+    /// ```
+    /// use bumpalo_try::BumpaloExtend;
+    /// let bump = bumpalo::Bump::new();
+    /// let x = bump.try_alloc_slice_fill_with_option(5, |i| Some(5 * (i + 1)));
+    /// assert_eq!(x.unwrap().unwrap(), &[5, 10, 15, 20, 25]);
+    /// ```
+    ///
+    /// ```
+    /// use bumpalo_try::BumpaloExtend;
+    /// let bump = bumpalo::Bump::new();
+    /// let x = bump.try_alloc_slice_fill_with_option(5, |i| None::<usize>);
+    /// assert_eq!(x.unwrap(), None);
+    /// ```
+    fn try_alloc_slice_fill_with_option<T, F>(&self, len: usize, mut f: F) -> Result<Option<&mut [T]>, bumpalo::AllocErr>
+    where
+        F: FnMut(usize) -> Option<T> {
+        match self.try_alloc_slice_fill_with_result(len, |i| f(i).ok_or(())) {
+            Ok(slice) => Ok(Some(slice)),
+            Err(FillError::Alloc(e)) => Err(e),
+            Err(FillError::Closure(())) => Ok(None),
+        }
+    }
+
+    /// The non-panicking, iterator-driven twin of
+    /// [`BumpaloExtend::alloc_slice_fill_iter_result`].
+    ///
+    /// ## Panics
+    ///
+    /// Panics if the supplied iterator returns fewer elements than it promised.
+    ///
+    /// ## Examples
+    ///
+    /// ```
+    /// use bumpalo_try::{BumpaloExtend, FillError};
+    /// let bump = bumpalo::Bump::new();
+    /// let x: Result<&mut [i32], FillError<()>> = bump.try_alloc_slice_fill_iter_result([2, 3, 5].iter().cloned().map(|i| Ok(i * i)));
+    /// assert_eq!(x.unwrap(), [4, 9, 25]);
+    /// ```
+    fn try_alloc_slice_fill_iter_result<T, E, I>(&self, iter: I) -> Result<&mut [T], FillError<E>>
+    where
+        I: IntoIterator<Item = Result<T, E>>,
+        I::IntoIter: ExactSizeIterator {
+        let mut iter = iter.into_iter();
+        self.try_alloc_slice_fill_with_result(iter.len(), |_| {
+            iter.next().expect("Iterator supplied too few elements")
+        })
+    }
+
+    /// The non-panicking, iterator-driven twin of
+    /// [`BumpaloExtend::alloc_slice_fill_iter_option`].
+    ///
+    /// ## Panics
+    ///
+    /// Panics if the supplied iterator returns fewer elements than it promised.
+    ///
+    /// ## Examples
+    ///
+    /// ```
+    /// use bumpalo_try::BumpaloExtend;
+    /// let bump = bumpalo::Bump::new();
+    /// let x = bump.try_alloc_slice_fill_iter_option([2i32, 3, 5].iter().cloned().map(|i| i.checked_pow(2)));
+    /// assert_eq!(x.unwrap().unwrap(), [4, 9, 25]);
+    /// ```
+    fn try_alloc_slice_fill_iter_option<T, I>(&self, iter: I) -> Result<Option<&mut [T]>, bumpalo::AllocErr>
+    where
+        I: IntoIterator<Item = Option<T>>,
+        I::IntoIter: ExactSizeIterator {
+        let mut iter = iter.into_iter();
+        self.try_alloc_slice_fill_with_option(iter.len(), |_| {
+            iter.next().expect("Iterator supplied too few elements")
+        })
+    }
+
+    /// Builds a new `&str` in this `Bump` by encoding each `char` yielded by
+    /// the iterator, early exiting if the iterator returns Err.
+    ///
+    /// This is the `&str` counterpart to the slice fill methods: the partial
+    /// string built so far is discarded as soon as an element fails.
+    ///
+    /// ## Examples
+    ///
+    /// ```
+    /// use bumpalo_try::BumpaloExtend;
+    /// let bump = bumpalo::Bump::new();
+    /// let x: Result<&mut str, ()> = bump.alloc_str_fill_iter_result("abc".chars().map(Ok));
+    /// assert_eq!(x.unwrap(), "abc");
+    /// ```
+    ///
+    /// ```
+    /// use bumpalo_try::BumpaloExtend;
+    /// let bump = bumpalo::Bump::new();
+    /// let x: Result<&mut str, ()> = bump.alloc_str_fill_iter_result(['a', 'b'].into_iter().map(Ok).chain([Err(())]));
+    /// assert_eq!(x, Err(()));
+    /// ```
+    fn alloc_str_fill_iter_result<E, I>(&self, iter: I) -> Result<&mut str, E>
+    where
+        I: IntoIterator<Item = Result<char, E>>;
+
+    /// Builds a new `&str` in this `Bump` by concatenating each `&str` chunk
+    /// yielded by the iterator, early exiting if the iterator returns Err.
+    ///
+    /// ## Examples
+    ///
+    /// ```
+    /// use bumpalo_try::BumpaloExtend;
+    /// let bump = bumpalo::Bump::new();
+    /// let x: Result<&mut str, ()> = bump.alloc_str_fill_iter_result_str(["ab", "cd"].into_iter().map(Ok));
+    /// assert_eq!(x.unwrap(), "abcd");
+    /// ```
+    ///
+    /// ```
+    /// use bumpalo_try::BumpaloExtend;
+    /// let bump = bumpalo::Bump::new();
+    /// let x: Result<&mut str, ()> = bump.alloc_str_fill_iter_result_str(["ab", "cd"].into_iter().map(Ok).chain([Err(())]));
+    /// assert_eq!(x, Err(()));
+    /// ```
+    fn alloc_str_fill_iter_result_str<'a, E, I>(&self, iter: I) -> Result<&mut str, E>
+    where
+        I: IntoIterator<Item = Result<&'a str, E>>;
 }
 
 impl BumpaloExtend for Bump {
@@ -142,14 +397,220 @@ impl BumpaloExtend for Bump {
         let dst = self.alloc_layout(layout).cast::<T>();
 
         unsafe {
+            let mut guard = FillGuard { dst: dst.as_ptr(), initialized: 0 };
             for i in 0..len {
                 let v = f(i)?;
                 ptr::write(dst.as_ptr().add(i), v);
+                guard.initialized += 1;
+            }
+            mem::forget(guard);
+
+            let result = slice::from_raw_parts_mut(dst.as_ptr(), len);
+            debug_assert_eq!(Layout::for_value(result), layout);
+            Ok(result)
+        }
+    }
+
+    fn alloc_slice_fill_iter_result_unsized<T, E, I>(&self, iter: I) -> Result<&mut [T], E>
+    where
+        I: IntoIterator<Item = Result<T, E>>,
+    {
+        let mut vec = BumpVec::new_in(self);
+        for item in iter {
+            vec.push(item?);
+        }
+        Ok(vec.into_bump_slice_mut())
+    }
+
+    fn try_alloc_slice_fill_with_result<T, E, F>(&self, len: usize, mut f: F) -> Result<&mut [T], FillError<E>>
+    where
+        F: FnMut(usize) -> Result<T, E>,
+    {
+        let layout = Layout::array::<T>(len).map_err(|_| FillError::Alloc(bumpalo::AllocErr))?;
+        let dst = self
+            .try_alloc_layout(layout)
+            .map_err(FillError::Alloc)?
+            .cast::<T>();
+
+        unsafe {
+            let mut guard = FillGuard { dst: dst.as_ptr(), initialized: 0 };
+            for i in 0..len {
+                let v = f(i).map_err(FillError::Closure)?;
+                ptr::write(dst.as_ptr().add(i), v);
+                guard.initialized += 1;
             }
+            mem::forget(guard);
 
             let result = slice::from_raw_parts_mut(dst.as_ptr(), len);
             debug_assert_eq!(Layout::for_value(result), layout);
             Ok(result)
         }
     }
+
+    fn alloc_str_fill_iter_result<E, I>(&self, iter: I) -> Result<&mut str, E>
+    where
+        I: IntoIterator<Item = Result<char, E>>,
+    {
+        let mut s = bumpalo::collections::String::new_in(self);
+        for c in iter {
+            s.push(c?);
+        }
+        Ok(bump_string_into_mut_str(s))
+    }
+
+    fn alloc_str_fill_iter_result_str<'a, E, I>(&self, iter: I) -> Result<&mut str, E>
+    where
+        I: IntoIterator<Item = Result<&'a str, E>>,
+    {
+        let mut s = bumpalo::collections::String::new_in(self);
+        for chunk in iter {
+            s.push_str(chunk?);
+        }
+        Ok(bump_string_into_mut_str(s))
+    }
+}
+
+/// Extension trait for collecting a fallible iterator of `Result`s directly
+/// into the bump arena, mirroring the shape of bumpalo's own `CollectIn`.
+///
+/// ## Examples
+///
+/// ```
+/// use bumpalo_try::TryCollectIn;
+/// let bump = bumpalo::Bump::new();
+/// let x: Result<&mut [i32], ()> = ["2", "3", "5"].iter().map(|s| s.parse().map_err(|_| ())).try_collect_in(&bump);
+/// assert_eq!(x.unwrap(), [2, 3, 5]);
+/// ```
+///
+/// ```
+/// use bumpalo_try::TryCollectIn;
+/// let bump = bumpalo::Bump::new();
+/// let x: Result<&mut [i32], ()> = ["2", "x", "5"].iter().map(|s| s.parse().map_err(|_| ())).try_collect_in(&bump);
+/// assert_eq!(x, Err(()));
+/// ```
+pub trait TryCollectIn<T, E>: IntoIterator<Item = Result<T, E>> + Sized {
+    /// Collects this iterator into a bump-allocated slice, bailing out on the
+    /// first `Err`.
+    fn try_collect_in(self, bump: &Bump) -> Result<&mut [T], E>
+    where
+        Self::IntoIter: ExactSizeIterator;
+
+    /// Collects this iterator into a bump-allocated `Vec`, so the result can
+    /// keep growing after collection. Unlike [`TryCollectIn::try_collect_in`],
+    /// this doesn't require an `ExactSizeIterator`.
+    fn try_collect_in_vec(self, bump: &Bump) -> Result<BumpVec<'_, T>, E>;
+}
+
+impl<T, E, I> TryCollectIn<T, E> for I
+where
+    I: IntoIterator<Item = Result<T, E>>,
+{
+    fn try_collect_in(self, bump: &Bump) -> Result<&mut [T], E>
+    where
+        Self::IntoIter: ExactSizeIterator,
+    {
+        bump.alloc_slice_fill_iter_result(self)
+    }
+
+    fn try_collect_in_vec(self, bump: &Bump) -> Result<BumpVec<'_, T>, E> {
+        let iter = self.into_iter();
+        let mut vec = BumpVec::with_capacity_in(iter.size_hint().0, bump);
+        for item in iter {
+            vec.push(item?);
+        }
+        Ok(vec)
+    }
+}
+
+/// The `Option` twin of [`TryCollectIn`], for iterators that signal failure
+/// with `None` instead of `Err`.
+///
+/// ## Examples
+///
+/// ```
+/// use bumpalo_try::TryCollectInOption;
+/// let bump = bumpalo::Bump::new();
+/// let x: Option<&mut [i32]> = [2i32, 3, 5].iter().map(|i| i.checked_pow(2)).try_collect_in(&bump);
+/// assert_eq!(x.unwrap(), [4, 9, 25]);
+/// ```
+///
+/// ```
+/// use bumpalo_try::TryCollectInOption;
+/// let bump = bumpalo::Bump::new();
+/// let x: Option<&mut [i32]> = [2, 3, i32::MAX].iter().map(|i| i.checked_pow(2)).try_collect_in(&bump);
+/// assert_eq!(x, None);
+/// ```
+pub trait TryCollectInOption<T>: IntoIterator<Item = Option<T>> + Sized {
+    /// Collects this iterator into a bump-allocated slice, bailing out on the
+    /// first `None`.
+    fn try_collect_in(self, bump: &Bump) -> Option<&mut [T]>
+    where
+        Self::IntoIter: ExactSizeIterator;
+
+    /// Collects this iterator into a bump-allocated `Vec`, so the result can
+    /// keep growing after collection.
+    fn try_collect_in_vec(self, bump: &Bump) -> Option<BumpVec<'_, T>>;
+}
+
+impl<T, I> TryCollectInOption<T> for I
+where
+    I: IntoIterator<Item = Option<T>>,
+{
+    fn try_collect_in(self, bump: &Bump) -> Option<&mut [T]>
+    where
+        Self::IntoIter: ExactSizeIterator,
+    {
+        bump.alloc_slice_fill_iter_option(self)
+    }
+
+    fn try_collect_in_vec(self, bump: &Bump) -> Option<BumpVec<'_, T>> {
+        self.into_iter()
+            .map(|v| v.ok_or(()))
+            .try_collect_in_vec(bump)
+            .ok()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::Cell;
+
+    struct DropCounter<'a>(&'a Cell<usize>);
+
+    impl Drop for DropCounter<'_> {
+        fn drop(&mut self) {
+            self.0.set(self.0.get() + 1);
+        }
+    }
+
+    #[test]
+    fn fill_with_result_drops_the_initialized_prefix_on_err() {
+        let bump = Bump::new();
+        let drops = Cell::new(0);
+        let result = bump.alloc_slice_fill_with_result(5, |i| {
+            if i == 3 {
+                Err(())
+            } else {
+                Ok(DropCounter(&drops))
+            }
+        });
+
+        assert!(result.is_err());
+        assert_eq!(drops.get(), 3);
+    }
+
+    #[test]
+    fn fill_with_result_does_not_drop_on_success() {
+        let bump = Bump::new();
+        let drops = Cell::new(0);
+        let result = bump.alloc_slice_fill_with_result(5, |_| Ok::<_, ()>(DropCounter(&drops)));
+
+        assert!(result.is_ok());
+        assert_eq!(drops.get(), 0);
+
+        drop(result);
+        drop(bump);
+        assert_eq!(drops.get(), 0);
+    }
 }
\ No newline at end of file